@@ -5,7 +5,7 @@
 //! using Haiku's native kernel kit functions: `find_thread`, `set_thread_priority`,
 //! and `get_thread_info`.
 
-use crate::{Error, ThreadPriority, ThreadPriorityValue};
+use crate::{Error, NamedPriority, ThreadPriority, ThreadPriorityValue};
 
 /// An alias type for a thread id.
 /// On Haiku, we use the native `thread_id` type (i32) rather than pthread_t.
@@ -33,8 +33,16 @@ unsafe extern "C" {
     fn set_thread_priority(thread: i32, new_priority: i32) -> i32;
     // Note: get_thread_info is a macro in Haiku, the actual function is _get_thread_info
     fn _get_thread_info(id: i32, info: *mut ThreadInfo, size: libc::size_t) -> i32;
+    fn rename_thread(thread: i32, new_name: *const libc::c_char) -> i32;
+    // BeOS-era compatibility call, still exported by libroot, that reports
+    // the number of logical CPUs in the system.
+    fn count_cpus() -> i32;
 }
 
+/// The maximum length of a Haiku thread name, including the NUL terminator
+/// (`B_OS_NAME_LENGTH`).
+const B_OS_NAME_LENGTH: usize = 32;
+
 /// Minimum thread priority value on Haiku
 pub const HAIKU_MIN_PRIORITY: i32 = 0;
 /// Maximum thread priority value on Haiku
@@ -42,6 +50,35 @@ pub const HAIKU_MAX_PRIORITY: i32 = 120;
 /// Default/normal thread priority value on Haiku
 pub const HAIKU_NORMAL_PRIORITY: i32 = 10;
 
+// The native kernel priority bands documented in Haiku's OS.h. These are the
+// values the scheduler actually treats specially, as opposed to an even
+// linear split of the 0-120 range.
+const B_IDLE_PRIORITY: i32 = 0;
+const B_LOWEST_ACTIVE_PRIORITY: i32 = 1;
+const B_LOW_PRIORITY: i32 = 5;
+const B_NORMAL_PRIORITY: i32 = HAIKU_NORMAL_PRIORITY;
+const B_DISPLAY_PRIORITY: i32 = 15;
+const B_URGENT_DISPLAY_PRIORITY: i32 = 20;
+const B_REAL_TIME_DISPLAY_PRIORITY: i32 = 100;
+const B_URGENT_PRIORITY: i32 = 110;
+const B_REAL_TIME_PRIORITY: i32 = HAIKU_MAX_PRIORITY;
+
+/// Maps a portable [`NamedPriority`] onto Haiku's native kernel priority
+/// bands, rather than linearly rescaling it like [`ThreadPriority::Crossplatform`] does.
+fn named_priority_to_haiku(named: NamedPriority) -> i32 {
+    match named {
+        NamedPriority::Idle => B_IDLE_PRIORITY,
+        NamedPriority::Lowest => B_LOWEST_ACTIVE_PRIORITY,
+        NamedPriority::Low => B_LOW_PRIORITY,
+        NamedPriority::Normal => B_NORMAL_PRIORITY,
+        NamedPriority::Display => B_DISPLAY_PRIORITY,
+        NamedPriority::UrgentDisplay => B_URGENT_DISPLAY_PRIORITY,
+        NamedPriority::RealtimeDisplay => B_REAL_TIME_DISPLAY_PRIORITY,
+        NamedPriority::Urgent => B_URGENT_PRIORITY,
+        NamedPriority::Realtime => B_REAL_TIME_PRIORITY,
+    }
+}
+
 /// Proxy structure to maintain compatibility with unix module
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct ScheduleParams {
@@ -73,13 +110,35 @@ pub enum RealtimeThreadSchedulePolicy {
     Fifo,
     /// A round-robin policy
     RoundRobin,
+    /// The POSIX sporadic server policy (`SCHED_SPORADIC`).
+    ///
+    /// Haiku has no equivalent scheduling policy, so [`Self::to_posix`]
+    /// always fails for this variant (this is a hard platform limitation,
+    /// not a missing mapping); callers should fall back to
+    /// [`RealtimeThreadSchedulePolicy::RoundRobin`] instead of silently
+    /// running under the wrong policy.
+    Sporadic {
+        /// The priority the thread runs at while its budget lasts.
+        sched_priority: libc::c_int,
+        /// The priority the thread drops to once its budget is consumed.
+        low_priority: libc::c_int,
+        /// The initial execution budget.
+        init_budget: std::time::Duration,
+        /// The replenishment period.
+        repl_period: std::time::Duration,
+        /// The maximum number of pending replenishments.
+        max_repl: libc::c_int,
+    },
 }
 
 impl RealtimeThreadSchedulePolicy {
-    fn to_posix(self) -> libc::c_int {
+    fn to_posix(self) -> Result<libc::c_int, Error> {
         match self {
-            RealtimeThreadSchedulePolicy::Fifo => 1,      // SCHED_FIFO
-            RealtimeThreadSchedulePolicy::RoundRobin => 2, // SCHED_RR
+            RealtimeThreadSchedulePolicy::Fifo => Ok(1),       // SCHED_FIFO
+            RealtimeThreadSchedulePolicy::RoundRobin => Ok(2), // SCHED_RR
+            RealtimeThreadSchedulePolicy::Sporadic { .. } => {
+                Err(Error::Priority("Haiku does not support SCHED_SPORADIC"))
+            }
         }
     }
 }
@@ -95,9 +154,9 @@ pub enum ThreadSchedulePolicy {
 
 impl ThreadSchedulePolicy {
     /// Converts to a POSIX policy value (for compatibility)
-    pub fn to_posix(self) -> libc::c_int {
+    pub fn to_posix(self) -> Result<libc::c_int, Error> {
         match self {
-            ThreadSchedulePolicy::Normal(p) => p.to_posix(),
+            ThreadSchedulePolicy::Normal(p) => Ok(p.to_posix()),
             ThreadSchedulePolicy::Realtime(p) => p.to_posix(),
         }
     }
@@ -164,6 +223,9 @@ impl ThreadPriority {
         match self {
             ThreadPriority::Min => Self::min_value_for_policy(policy),
             ThreadPriority::Max => Self::max_value_for_policy(policy),
+            ThreadPriority::Named(named) => {
+                Self::to_allowed_value_for_policy(named_priority_to_haiku(named), policy)
+            }
             ThreadPriority::Crossplatform(ThreadPriorityValue(p)) => {
                 // Map 0-99 range to Haiku's 0-120 range
                 let haiku_priority = (p as i32 * HAIKU_MAX_PRIORITY) / 99;
@@ -238,6 +300,103 @@ pub fn get_current_thread_priority() -> Result<ThreadPriority, Error> {
     get_thread_priority(thread_native_id())
 }
 
+/// Gets a thread's name using Haiku's `_get_thread_info`.
+pub fn get_thread_name(native: ThreadId) -> Result<String, Error> {
+    let mut info: ThreadInfo = unsafe { std::mem::zeroed() };
+    let result = unsafe { _get_thread_info(native, &mut info, std::mem::size_of::<ThreadInfo>()) };
+
+    if result != 0 {
+        return Err(Error::OS(result));
+    }
+
+    // `name` is not guaranteed to be NUL-terminated if it fills the whole
+    // buffer, so find the terminator ourselves rather than assuming one.
+    let nul_position = info
+        .name
+        .iter()
+        .position(|&c| c == 0)
+        .unwrap_or(info.name.len());
+    let bytes: Vec<u8> = info.name[..nul_position]
+        .iter()
+        .map(|&c| c as u8)
+        .collect();
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Sets a thread's name using Haiku's native `rename_thread`.
+///
+/// Haiku's thread names are fixed-size, NUL-terminated buffers of
+/// [`B_OS_NAME_LENGTH`] bytes, so `name` is truncated to 31 bytes plus the
+/// NUL terminator rather than overflowing the kernel's copy.
+pub fn set_thread_name(native: ThreadId, name: &str) -> Result<(), Error> {
+    let max_len = B_OS_NAME_LENGTH - 1;
+    let truncated = if name.len() > max_len {
+        // Truncate on a char boundary so we don't split a multi-byte
+        // UTF-8 sequence in half.
+        let mut end = max_len;
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        &name[..end]
+    } else {
+        name
+    };
+
+    let c_name = std::ffi::CString::new(truncated)
+        .map_err(|_| Error::Priority("The thread name must not contain a NUL byte"))?;
+
+    let result = unsafe { rename_thread(native, c_name.as_ptr()) };
+    if result != 0 {
+        Err(Error::OS(result))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the number of logical CPUs known to the system.
+fn cpu_count() -> usize {
+    let count = unsafe { count_cpus() };
+    count.max(1) as usize
+}
+
+/// Attempts to pin a thread to the given set of logical CPU indices.
+///
+/// Haiku does not expose a public API to hard-pin a thread to specific
+/// CPUs, so this is a best-effort call: the requested indices are
+/// validated against the system's actual CPU count, but
+/// [`Error::Priority`] is always returned to make it clear to the caller
+/// that the pinning itself could not be honored.
+pub fn set_thread_affinity(_native: ThreadId, cpus: &[usize]) -> Result<(), Error> {
+    let available = cpu_count();
+    if cpus.iter().any(|&cpu| cpu >= available) {
+        return Err(Error::Priority(
+            "requested CPU index is out of range for this system",
+        ));
+    }
+
+    Err(Error::Priority(
+        "Haiku does not expose an API to pin a thread to specific CPUs",
+    ))
+}
+
+/// Reports the set of CPUs a thread is eligible to run on.
+///
+/// Since Haiku has no hard-pinning API, every thread is eligible to run on
+/// any CPU the kernel knows about.
+pub fn get_thread_affinity(_native: ThreadId) -> Result<Vec<usize>, Error> {
+    Ok((0..cpu_count()).collect())
+}
+
+/// Reports the logical CPU the calling thread is currently running on.
+///
+/// Haiku does not expose this information through a public API.
+pub fn current_cpu_id() -> Result<usize, Error> {
+    Err(Error::Priority(
+        "Haiku does not expose the calling thread's current CPU",
+    ))
+}
+
 /// A helper trait for other threads to implement to be able to call methods
 /// on threads themselves.
 pub trait ThreadExt {
@@ -252,37 +411,62 @@ pub trait ThreadExt {
     /// ```
     fn get_native_id(&self) -> Result<ThreadId, Error>;
 
-    /// Gets the current thread's priority.
+    /// Gets the thread's priority.
     fn get_priority(&self) -> Result<ThreadPriority, Error> {
-        get_current_thread_priority()
+        get_thread_priority(self.get_native_id()?)
     }
 
-    /// Sets the current thread's priority.
+    /// Sets the thread's priority.
     fn set_priority(&self, priority: ThreadPriority) -> Result<(), Error> {
-        set_current_thread_priority(priority)
+        let native = self.get_native_id()?;
+        let policy = thread_schedule_policy_param(native)?.0;
+        set_thread_priority_and_policy(native, priority, policy)
+    }
+
+    /// Gets the thread's name.
+    fn get_name(&self) -> Result<String, Error> {
+        get_thread_name(self.get_native_id()?)
     }
 
-    /// Gets the current thread's schedule policy.
+    /// Sets the thread's name.
+    fn set_name(&self, name: &str) -> Result<(), Error> {
+        set_thread_name(self.get_native_id()?, name)
+    }
+
+    /// Attempts to pin the thread to the given set of logical CPU indices.
+    fn set_affinity(&self, cpus: &[usize]) -> Result<(), Error> {
+        set_thread_affinity(self.get_native_id()?, cpus)
+    }
+
+    /// Reports the set of CPUs the thread is eligible to run on.
+    fn get_affinity(&self) -> Result<Vec<usize>, Error> {
+        get_thread_affinity(self.get_native_id()?)
+    }
+
+    /// Gets the thread's schedule policy.
     fn get_schedule_policy(&self) -> Result<ThreadSchedulePolicy, Error> {
-        thread_schedule_policy()
+        Ok(thread_schedule_policy_param(self.get_native_id()?)?.0)
     }
 
-    /// Returns current thread's schedule policy and parameters.
+    /// Returns the thread's schedule policy and parameters.
     fn get_schedule_policy_param(&self) -> Result<(ThreadSchedulePolicy, ScheduleParams), Error> {
-        thread_schedule_policy_param(thread_native_id())
+        thread_schedule_policy_param(self.get_native_id()?)
     }
 
-    /// Sets current thread's schedule policy and priority.
+    /// Sets the thread's schedule policy and priority.
     fn set_priority_and_policy(
         &self,
         policy: ThreadSchedulePolicy,
         priority: ThreadPriority,
     ) -> Result<(), Error> {
-        set_thread_priority_and_policy(thread_native_id(), priority, policy)
+        set_thread_priority_and_policy(self.get_native_id()?, priority, policy)
     }
 }
 
 /// Auto-implementation of this trait for the [`std::thread::Thread`].
+///
+/// `std::thread::Thread` has no portable way to recover another thread's
+/// native id, so this impl remains limited to the calling thread.
 impl ThreadExt for std::thread::Thread {
     fn get_native_id(&self) -> Result<ThreadId, Error> {
         if self.id() == std::thread::current().id() {
@@ -294,3 +478,33 @@ impl ThreadExt for std::thread::Thread {
         }
     }
 }
+
+/// A handle to a specific Haiku thread, identified by its native
+/// [`ThreadId`].
+///
+/// Unlike `ThreadId` (a bare `i32`), this type implements [`ThreadExt`]
+/// deliberately: blanket-implementing the trait on the raw alias would make
+/// `.get_priority()`/`.set_priority()` callable on any `i32` in scope, even
+/// one that was never obtained from [`thread_native_id`] or a similar
+/// source. Wrapping the id keeps that opt-in explicit.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ThreadHandle(ThreadId);
+
+impl ThreadHandle {
+    /// Wraps a native [`ThreadId`], for example one obtained from
+    /// [`thread_native_id`] or received from another thread.
+    pub fn new(id: ThreadId) -> Self {
+        Self(id)
+    }
+}
+
+/// `find_thread`, `_get_thread_info` and `set_thread_priority` all take an
+/// explicit `thread_id` and require no "is it me" check, so a supervisor
+/// that already knows the [`ThreadId`] of a worker thread it spawned can
+/// wrap it in a [`ThreadHandle`] to query or adjust that thread directly,
+/// instead of only being able to self-tune the calling thread.
+impl ThreadExt for ThreadHandle {
+    fn get_native_id(&self) -> Result<ThreadId, Error> {
+        Ok(self.0)
+    }
+}
@@ -0,0 +1,128 @@
+//! This crate is an attempt to provide a cross-platform way of managing
+//! thread priorities and scheduling policies.
+//!
+//! Each supported platform exposes the same portable API
+//! ([`ThreadPriority`], [`ThreadSchedulePolicy`], [`ThreadExt`]) backed by a
+//! platform-specific module (for example [`unix`] for POSIX systems or
+//! [`haiku`] for Haiku), so that application code can be written once and
+//! simply recompiled for the target platform.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use std::ops::RangeInclusive;
+
+#[cfg(all(unix, not(target_os = "haiku")))]
+pub mod unix;
+#[cfg(all(unix, not(target_os = "haiku")))]
+pub use unix::*;
+
+#[cfg(target_os = "haiku")]
+pub mod haiku;
+#[cfg(target_os = "haiku")]
+pub use haiku::*;
+
+/// The error type used through out this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A non-specific, platform-related error, with a human-readable
+    /// description of what went wrong.
+    Priority(&'static str),
+    /// An OS-specific error code. Its meaning depends on the platform and
+    /// the call that returned it (for instance `errno` on POSIX systems).
+    OS(i32),
+    /// The requested priority value is outside of the range allowed by the
+    /// current scheduling policy.
+    PriorityNotInRange(RangeInclusive<i32>),
+}
+
+/// A cross-platform representation of a thread priority, expressed as a
+/// value between 0 and 99, inclusive, where 99 is the highest possible
+/// priority.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ThreadPriorityValue(pub(crate) u8);
+
+/// The maximum value of the cross-platform priority range.
+pub const MAX_CROSSPLATFORM_PRIORITY: u8 = 99;
+
+impl TryFrom<u8> for ThreadPriorityValue {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if value > MAX_CROSSPLATFORM_PRIORITY {
+            Err("The value must be in range [0; 99]")
+        } else {
+            Ok(Self(value))
+        }
+    }
+}
+
+impl From<ThreadPriorityValue> for u8 {
+    fn from(value: ThreadPriorityValue) -> Self {
+        value.0
+    }
+}
+
+/// An os-specific priority value, used as an escape hatch for cases where
+/// [`ThreadPriority::Crossplatform`] doesn't fit the bill and the caller
+/// knows the exact native value it wants to use.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ThreadPriorityOsValue(pub u32);
+
+/// A portable representation of a thread's priority.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ThreadPriority {
+    /// The minimum possible priority allowed by the active scheduling
+    /// policy.
+    Min,
+    /// A named, portable priority preset. See [`NamedPriority`] for the
+    /// available bands and how each platform maps them onto its native
+    /// priority range.
+    Named(NamedPriority),
+    /// A value in the portable 0-99 range.
+    Crossplatform(ThreadPriorityValue),
+    /// A raw, platform-specific priority value.
+    Os(ThreadPriorityOsValue),
+    /// The maximum possible priority allowed by the active scheduling
+    /// policy.
+    Max,
+}
+
+/// A portable vocabulary of named priority bands.
+///
+/// These mirror the bands that platform schedulers document for
+/// interactive and real-time work (for example Haiku's kernel priority
+/// bands), so that callers don't need to pick an arbitrary 0-99 number and
+/// hope it lands in a sensible place on every platform.
+///
+/// On Haiku each band maps to its own native kernel priority constant, so
+/// the bands are strictly increasing in the order listed below. On POSIX
+/// systems the bands are resolved against the min/max priority of the
+/// active scheduling policy: `Idle` is the minimum, `Lowest` and `Low` are
+/// both min + 1, `Normal`, `Display` and `UrgentDisplay` are all the
+/// midpoint, `RealtimeDisplay` and `Urgent` are both max - 2, and
+/// `Realtime` is max - 1, each clamped to the policy's range. Several bands
+/// therefore share a value there.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum NamedPriority {
+    /// The lowest possible priority, reserved for idle-time work.
+    Idle,
+    /// At or below [`NamedPriority::Low`], for background work that should
+    /// rarely preempt anything else.
+    Lowest,
+    /// Below-normal priority for background work.
+    Low,
+    /// The default priority most threads run at.
+    Normal,
+    /// At or above normal priority, for threads that feed the display.
+    Display,
+    /// A priority band for display work that must not be starved.
+    UrgentDisplay,
+    /// A real-time band reserved for display work.
+    RealtimeDisplay,
+    /// A priority band for urgent, latency-sensitive work.
+    Urgent,
+    /// The highest real-time priority, reserved for work that must
+    /// preempt everything else.
+    Realtime,
+}
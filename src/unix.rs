@@ -0,0 +1,531 @@
+//! This module defines the POSIX thread control, shared by every unix-like
+//! platform (Linux, macOS, the BSDs, ...) except Haiku, which has its own
+//! native API and is implemented in [`crate::haiku`] instead.
+
+use crate::{Error, NamedPriority, ThreadPriority, ThreadPriorityValue};
+
+/// An alias type for a thread id.
+pub type ThreadId = libc::pthread_t;
+
+/// Proxy structure to maintain compatibility with the [`libc`] crate.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ScheduleParams {
+    /// The thread priority value.
+    pub sched_priority: libc::c_int,
+}
+
+/// The normal (non-realtime) parsing of the scheduling policies.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum NormalThreadSchedulePolicy {
+    /// The standard round-robin time-sharing policy.
+    Other,
+    /// For "batch" style execution of processes.
+    Batch,
+    /// Very low priority, for background tasks.
+    Idle,
+}
+
+impl NormalThreadSchedulePolicy {
+    fn to_posix(self) -> libc::c_int {
+        match self {
+            NormalThreadSchedulePolicy::Other => libc::SCHED_OTHER,
+            #[cfg(target_os = "linux")]
+            NormalThreadSchedulePolicy::Batch => libc::SCHED_BATCH,
+            #[cfg(not(target_os = "linux"))]
+            NormalThreadSchedulePolicy::Batch => libc::SCHED_OTHER,
+            #[cfg(target_os = "linux")]
+            NormalThreadSchedulePolicy::Idle => libc::SCHED_IDLE,
+            #[cfg(not(target_os = "linux"))]
+            NormalThreadSchedulePolicy::Idle => libc::SCHED_OTHER,
+        }
+    }
+}
+
+/// Realtime scheduling policies.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum RealtimeThreadSchedulePolicy {
+    /// A first-in, first-out policy.
+    Fifo,
+    /// A round-robin policy.
+    RoundRobin,
+    /// The POSIX sporadic server policy (`SCHED_SPORADIC`).
+    ///
+    /// The thread runs at `sched_priority` while its execution budget
+    /// lasts. Once the budget is consumed the thread drops to
+    /// `low_priority`; the consumed budget is replenished one `repl_period`
+    /// after it began being consumed, capped at `max_repl` pending
+    /// replenishments.
+    ///
+    /// `libc` does not define `SCHED_SPORADIC` for any platform this crate
+    /// currently targets (only vxworks, qurt and QNX define it upstream),
+    /// so there is no POSIX policy value to hand to
+    /// `pthread_setschedparam` and [`Self::to_posix`] always returns
+    /// [`Error::Priority`] for this variant. The `sched_ss_*` fields this
+    /// variant models are therefore never actually populated on any
+    /// supported target; this is a known limitation, not an oversight.
+    Sporadic {
+        /// The priority the thread runs at while its budget lasts.
+        sched_priority: libc::c_int,
+        /// The priority the thread drops to once its budget is consumed.
+        low_priority: libc::c_int,
+        /// The initial execution budget.
+        init_budget: std::time::Duration,
+        /// The replenishment period.
+        repl_period: std::time::Duration,
+        /// The maximum number of pending replenishments.
+        max_repl: libc::c_int,
+    },
+}
+
+impl RealtimeThreadSchedulePolicy {
+    fn to_posix(self) -> Result<libc::c_int, Error> {
+        match self {
+            RealtimeThreadSchedulePolicy::Fifo => Ok(libc::SCHED_FIFO),
+            RealtimeThreadSchedulePolicy::RoundRobin => Ok(libc::SCHED_RR),
+            // See the doc comment on `Sporadic` above: no currently
+            // targeted platform's `libc` exposes `SCHED_SPORADIC`, so the
+            // sporadic policy cannot be selected on any supported target and
+            // this arm always fails. Callers should fall back to `RoundRobin` instead
+            // of silently running under the wrong policy.
+            RealtimeThreadSchedulePolicy::Sporadic { .. } => Err(Error::Priority(
+                "SCHED_SPORADIC is not supported on this platform",
+            )),
+        }
+    }
+}
+
+/// Thread schedule policy definition.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ThreadSchedulePolicy {
+    /// Normal (non-realtime) scheduling policies.
+    Normal(NormalThreadSchedulePolicy),
+    /// Realtime scheduling policies.
+    Realtime(RealtimeThreadSchedulePolicy),
+}
+
+impl ThreadSchedulePolicy {
+    /// Converts to a POSIX policy value.
+    pub fn to_posix(self) -> Result<libc::c_int, Error> {
+        match self {
+            ThreadSchedulePolicy::Normal(p) => Ok(p.to_posix()),
+            ThreadSchedulePolicy::Realtime(p) => p.to_posix(),
+        }
+    }
+}
+
+/// Returns current thread id, the current native pthread handle.
+#[inline(always)]
+pub fn thread_native_id() -> ThreadId {
+    unsafe { libc::pthread_self() }
+}
+
+impl ThreadPriority {
+    /// Returns the minimum allowed priority value for a policy.
+    pub fn min_value_for_policy(policy: ThreadSchedulePolicy) -> Result<libc::c_int, Error> {
+        let ret = unsafe { libc::sched_get_priority_min(policy.to_posix()?) };
+        if ret < 0 {
+            Err(Error::OS(ret))
+        } else {
+            Ok(ret)
+        }
+    }
+
+    /// Returns the maximum allowed priority value for a policy.
+    pub fn max_value_for_policy(policy: ThreadSchedulePolicy) -> Result<libc::c_int, Error> {
+        let ret = unsafe { libc::sched_get_priority_max(policy.to_posix()?) };
+        if ret < 0 {
+            Err(Error::OS(ret))
+        } else {
+            Ok(ret)
+        }
+    }
+
+    /// Checks that the passed priority value is within the range of allowed values.
+    pub fn to_allowed_value_for_policy(
+        priority: libc::c_int,
+        policy: ThreadSchedulePolicy,
+    ) -> Result<libc::c_int, Error> {
+        let min_priority = Self::min_value_for_policy(policy)?;
+        let max_priority = Self::max_value_for_policy(policy)?;
+        let allowed_range = min_priority..=max_priority;
+
+        if allowed_range.contains(&priority) {
+            Ok(priority)
+        } else {
+            Err(Error::PriorityNotInRange(allowed_range))
+        }
+    }
+
+    /// Resolves a [`NamedPriority`] band against the min/max priority values of
+    /// the active scheduling policy.
+    fn named_value_for_policy(
+        named: NamedPriority,
+        policy: ThreadSchedulePolicy,
+    ) -> Result<libc::c_int, Error> {
+        let min = Self::min_value_for_policy(policy)?;
+        let max = Self::max_value_for_policy(policy)?;
+        let mid = min + (max - min) / 2;
+
+        // Some policies (e.g. `SCHED_OTHER`, or every policy on platforms
+        // that don't report a real range) have a min/max range too narrow
+        // to fit every band distinctly; clamp instead of producing a value
+        // outside what the policy actually allows.
+        let value = match named {
+            NamedPriority::Idle => min,
+            NamedPriority::Lowest => min + 1,
+            NamedPriority::Low => min + 1,
+            NamedPriority::Normal => mid,
+            NamedPriority::Display => mid,
+            NamedPriority::UrgentDisplay => mid,
+            NamedPriority::RealtimeDisplay => max - 2,
+            NamedPriority::Urgent => max - 2,
+            NamedPriority::Realtime => max - 1,
+        };
+
+        Ok(value.clamp(min, max))
+    }
+
+    /// Converts the priority to a POSIX-compatible value.
+    pub fn to_posix(self, policy: ThreadSchedulePolicy) -> Result<libc::c_int, Error> {
+        match self {
+            ThreadPriority::Min => Self::min_value_for_policy(policy),
+            ThreadPriority::Max => Self::max_value_for_policy(policy),
+            ThreadPriority::Named(named) => Self::named_value_for_policy(named, policy),
+            ThreadPriority::Crossplatform(ThreadPriorityValue(p)) => {
+                let min = Self::min_value_for_policy(policy)?;
+                let max = Self::max_value_for_policy(policy)?;
+                let posix_priority = min + ((p as i32) * (max - min)) / 99;
+                Self::to_allowed_value_for_policy(posix_priority, policy)
+            }
+            ThreadPriority::Os(crate::ThreadPriorityOsValue(p)) => {
+                Self::to_allowed_value_for_policy(p as i32, policy)
+            }
+        }
+    }
+}
+
+/// Sets thread's priority and schedule policy.
+pub fn set_thread_priority_and_policy(
+    native: ThreadId,
+    priority: ThreadPriority,
+    policy: ThreadSchedulePolicy,
+) -> Result<(), Error> {
+    let posix_priority = priority.to_posix(policy)?;
+    let params = libc::sched_param {
+        sched_priority: posix_priority,
+    };
+    let ret = unsafe { libc::pthread_setschedparam(native, policy.to_posix()?, &params) };
+    if ret != 0 {
+        Err(Error::OS(ret))
+    } else {
+        Ok(())
+    }
+}
+
+/// Set current thread's priority.
+pub fn set_current_thread_priority(priority: ThreadPriority) -> Result<(), Error> {
+    let (policy, _) = thread_schedule_policy_param(thread_native_id())?;
+    set_thread_priority_and_policy(thread_native_id(), priority, policy)
+}
+
+/// Returns policy parameters (schedule policy and other schedule parameters)
+pub fn thread_schedule_policy_param(
+    native: ThreadId,
+) -> Result<(ThreadSchedulePolicy, libc::sched_param), Error> {
+    let mut policy = 0;
+    let mut params = libc::sched_param { sched_priority: 0 };
+
+    let ret =
+        unsafe { libc::pthread_getschedparam(native, &mut policy, &mut params) };
+
+    if ret != 0 {
+        return Err(Error::OS(ret));
+    }
+
+    let policy = match policy {
+        libc::SCHED_FIFO => ThreadSchedulePolicy::Realtime(RealtimeThreadSchedulePolicy::Fifo),
+        libc::SCHED_RR => ThreadSchedulePolicy::Realtime(RealtimeThreadSchedulePolicy::RoundRobin),
+        #[cfg(target_os = "linux")]
+        libc::SCHED_BATCH => ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Batch),
+        #[cfg(target_os = "linux")]
+        libc::SCHED_IDLE => ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Idle),
+        _ => ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Other),
+    };
+
+    Ok((policy, params))
+}
+
+/// Get the thread's priority value.
+pub fn get_thread_priority(native: ThreadId) -> Result<ThreadPriority, Error> {
+    let (_, params) = thread_schedule_policy_param(native)?;
+    Ok(ThreadPriority::Os(crate::ThreadPriorityOsValue(
+        params.sched_priority as u32,
+    )))
+}
+
+/// Get current thread's priority value.
+pub fn get_current_thread_priority() -> Result<ThreadPriority, Error> {
+    get_thread_priority(thread_native_id())
+}
+
+/// Pins a thread to the given set of logical CPU indices.
+#[cfg(target_os = "linux")]
+pub fn set_thread_affinity(native: ThreadId, cpus: &[usize]) -> Result<(), Error> {
+    let max_cpus = libc::CPU_SETSIZE as usize;
+    if cpus.iter().any(|&cpu| cpu >= max_cpus) {
+        return Err(Error::Priority(
+            "requested CPU index is out of range for `cpu_set_t`",
+        ));
+    }
+
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_ZERO(&mut set);
+        for &cpu in cpus {
+            libc::CPU_SET(cpu, &mut set);
+        }
+
+        let ret =
+            libc::pthread_setaffinity_np(native, std::mem::size_of::<libc::cpu_set_t>(), &set);
+        if ret != 0 {
+            Err(Error::OS(ret))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Reports the set of logical CPU indices a thread is pinned to.
+#[cfg(target_os = "linux")]
+pub fn get_thread_affinity(native: ThreadId) -> Result<Vec<usize>, Error> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        let ret =
+            libc::pthread_getaffinity_np(native, std::mem::size_of::<libc::cpu_set_t>(), &mut set);
+        if ret != 0 {
+            return Err(Error::OS(ret));
+        }
+
+        Ok((0..libc::CPU_SETSIZE as usize)
+            .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+            .collect())
+    }
+}
+
+/// Reports the logical CPU the calling thread is currently running on.
+#[cfg(target_os = "linux")]
+pub fn current_cpu_id() -> Result<usize, Error> {
+    let cpu = unsafe { libc::sched_getcpu() };
+    if cpu < 0 {
+        Err(Error::OS(cpu))
+    } else {
+        Ok(cpu as usize)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod apple_affinity {
+    use crate::Error;
+
+    #[allow(non_camel_case_types)]
+    type thread_t = u32;
+    #[allow(non_camel_case_types)]
+    type kern_return_t = i32;
+    #[allow(non_camel_case_types)]
+    type thread_policy_flavor_t = u32;
+
+    const THREAD_AFFINITY_POLICY: thread_policy_flavor_t = 4;
+    const THREAD_AFFINITY_POLICY_COUNT: u32 = 1;
+
+    #[repr(C)]
+    struct ThreadAffinityPolicyData {
+        affinity_tag: i32,
+    }
+
+    unsafe extern "C" {
+        fn pthread_mach_thread_np(thread: libc::pthread_t) -> thread_t;
+        fn thread_policy_set(
+            thread: thread_t,
+            flavor: thread_policy_flavor_t,
+            policy_info: *mut i32,
+            count: u32,
+        ) -> kern_return_t;
+        fn thread_policy_get(
+            thread: thread_t,
+            flavor: thread_policy_flavor_t,
+            policy_info: *mut i32,
+            count: *mut u32,
+            get_default: *mut i32,
+        ) -> kern_return_t;
+    }
+
+    /// macOS has no notion of pinning a thread to a specific logical CPU;
+    /// instead, threads sharing the same affinity tag are grouped together
+    /// by the scheduler. We use the first requested CPU index as that tag.
+    pub fn set_thread_affinity(native: libc::pthread_t, cpus: &[usize]) -> Result<(), Error> {
+        let tag = *cpus
+            .first()
+            .ok_or(Error::Priority("at least one CPU index must be given"))?
+            as i32;
+
+        let mut policy = ThreadAffinityPolicyData { affinity_tag: tag };
+        let thread = unsafe { pthread_mach_thread_np(native) };
+        let ret = unsafe {
+            thread_policy_set(
+                thread,
+                THREAD_AFFINITY_POLICY,
+                &mut policy as *mut ThreadAffinityPolicyData as *mut i32,
+                THREAD_AFFINITY_POLICY_COUNT,
+            )
+        };
+
+        if ret != 0 {
+            Err(Error::OS(ret))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn get_thread_affinity(native: libc::pthread_t) -> Result<Vec<usize>, Error> {
+        let mut policy = ThreadAffinityPolicyData { affinity_tag: 0 };
+        let mut count = THREAD_AFFINITY_POLICY_COUNT;
+        let mut get_default: i32 = 0;
+        let thread = unsafe { pthread_mach_thread_np(native) };
+        let ret = unsafe {
+            thread_policy_get(
+                thread,
+                THREAD_AFFINITY_POLICY,
+                &mut policy as *mut ThreadAffinityPolicyData as *mut i32,
+                &mut count,
+                &mut get_default,
+            )
+        };
+
+        if ret != 0 {
+            Err(Error::OS(ret))
+        } else {
+            Ok(vec![policy.affinity_tag as usize])
+        }
+    }
+}
+
+/// Pins a thread to the given set of logical CPU indices.
+///
+/// On macOS this does not pin to a specific CPU but groups threads sharing
+/// the same affinity tag together; the first entry of `cpus` is used as
+/// that tag.
+#[cfg(target_os = "macos")]
+pub fn set_thread_affinity(native: ThreadId, cpus: &[usize]) -> Result<(), Error> {
+    apple_affinity::set_thread_affinity(native, cpus)
+}
+
+/// Reports the affinity tag a thread was last assigned, as a single-element
+/// CPU set.
+#[cfg(target_os = "macos")]
+pub fn get_thread_affinity(native: ThreadId) -> Result<Vec<usize>, Error> {
+    apple_affinity::get_thread_affinity(native)
+}
+
+/// Reports the logical CPU the calling thread is currently running on.
+///
+/// macOS does not expose this information through a public API.
+#[cfg(target_os = "macos")]
+pub fn current_cpu_id() -> Result<usize, Error> {
+    Err(Error::Priority(
+        "macOS does not expose the calling thread's current CPU",
+    ))
+}
+
+/// Pins a thread to the given set of logical CPU indices.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn set_thread_affinity(_native: ThreadId, _cpus: &[usize]) -> Result<(), Error> {
+    Err(Error::Priority(
+        "CPU affinity is not supported on this platform",
+    ))
+}
+
+/// Reports the set of logical CPU indices a thread is pinned to.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn get_thread_affinity(_native: ThreadId) -> Result<Vec<usize>, Error> {
+    Err(Error::Priority(
+        "CPU affinity is not supported on this platform",
+    ))
+}
+
+/// Reports the logical CPU the calling thread is currently running on.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn current_cpu_id() -> Result<usize, Error> {
+    Err(Error::Priority(
+        "CPU affinity is not supported on this platform",
+    ))
+}
+
+/// A helper trait for other threads to implement to be able to call methods
+/// on threads themselves.
+pub trait ThreadExt {
+    /// Gets the native thread id.
+    fn get_native_id(&self) -> Result<ThreadId, Error>;
+
+    /// Gets the thread's priority.
+    fn get_priority(&self) -> Result<ThreadPriority, Error> {
+        get_thread_priority(self.get_native_id()?)
+    }
+
+    /// Sets the thread's priority.
+    fn set_priority(&self, priority: ThreadPriority) -> Result<(), Error> {
+        let native = self.get_native_id()?;
+        let (policy, _) = thread_schedule_policy_param(native)?;
+        set_thread_priority_and_policy(native, priority, policy)
+    }
+
+    /// Gets the thread's schedule policy.
+    fn get_schedule_policy(&self) -> Result<ThreadSchedulePolicy, Error> {
+        Ok(thread_schedule_policy_param(self.get_native_id()?)?.0)
+    }
+
+    /// Returns the thread's schedule policy and parameters.
+    fn get_schedule_policy_param(&self) -> Result<(ThreadSchedulePolicy, ScheduleParams), Error> {
+        let (policy, params) = thread_schedule_policy_param(self.get_native_id()?)?;
+        Ok((
+            policy,
+            ScheduleParams {
+                sched_priority: params.sched_priority,
+            },
+        ))
+    }
+
+    /// Pins the thread to the given set of logical CPU indices.
+    fn set_affinity(&self, cpus: &[usize]) -> Result<(), Error> {
+        set_thread_affinity(self.get_native_id()?, cpus)
+    }
+
+    /// Reports the set of CPUs the thread is pinned to.
+    fn get_affinity(&self) -> Result<Vec<usize>, Error> {
+        get_thread_affinity(self.get_native_id()?)
+    }
+
+    /// Sets the thread's schedule policy and priority.
+    fn set_priority_and_policy(
+        &self,
+        policy: ThreadSchedulePolicy,
+        priority: ThreadPriority,
+    ) -> Result<(), Error> {
+        set_thread_priority_and_policy(self.get_native_id()?, priority, policy)
+    }
+}
+
+/// Auto-implementation of this trait for the [`std::thread::Thread`].
+///
+/// `std::thread::Thread` has no portable way to recover another thread's
+/// native id, so this impl remains limited to the calling thread.
+impl ThreadExt for std::thread::Thread {
+    fn get_native_id(&self) -> Result<ThreadId, Error> {
+        if self.id() == std::thread::current().id() {
+            Ok(thread_native_id())
+        } else {
+            Err(Error::Priority(
+                "The `ThreadExt::get_native_id()` is currently limited to be called on the current thread.",
+            ))
+        }
+    }
+}
@@ -121,6 +121,131 @@ fn set_min_priority() {
     assert!(result.is_ok());
 }
 
+#[rstest]
+fn named_priorities_resolve_to_allowed_values(
+    #[values(
+        NamedPriority::Idle,
+        NamedPriority::Lowest,
+        NamedPriority::Low,
+        NamedPriority::Normal,
+        NamedPriority::Display,
+        NamedPriority::UrgentDisplay,
+        NamedPriority::RealtimeDisplay,
+        NamedPriority::Urgent,
+        NamedPriority::Realtime
+    )]
+    named: NamedPriority,
+) {
+    let policy = ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Other);
+    let result = ThreadPriority::Named(named).to_posix(policy);
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert!(value >= HAIKU_MIN_PRIORITY);
+    assert!(value <= HAIKU_MAX_PRIORITY);
+}
+
+#[test]
+fn named_priority_bands_are_ordered() {
+    let policy = ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Other);
+    let idle = ThreadPriority::Named(NamedPriority::Idle)
+        .to_posix(policy)
+        .unwrap();
+    let normal = ThreadPriority::Named(NamedPriority::Normal)
+        .to_posix(policy)
+        .unwrap();
+    let realtime = ThreadPriority::Named(NamedPriority::Realtime)
+        .to_posix(policy)
+        .unwrap();
+
+    assert!(idle < normal);
+    assert!(normal < realtime);
+}
+
+#[test]
+fn get_thread_name_works() {
+    let name = get_thread_name(thread_native_id());
+    assert!(name.is_ok());
+}
+
+#[test]
+fn set_and_get_thread_name() {
+    let thread_id = thread_native_id();
+    assert!(set_thread_name(thread_id, "test-thread").is_ok());
+    assert_eq!(get_thread_name(thread_id).unwrap(), "test-thread");
+}
+
+#[test]
+fn set_thread_name_truncates_long_names() {
+    let thread_id = thread_native_id();
+    let long_name = "a".repeat(64);
+    assert!(set_thread_name(thread_id, &long_name).is_ok());
+    assert!(get_thread_name(thread_id).unwrap().len() <= 31);
+}
+
+#[test]
+fn thread_ext_get_and_set_name() {
+    let thread = std::thread::current();
+    assert!(thread.set_name("ext-thread").is_ok());
+    assert_eq!(thread.get_name().unwrap(), "ext-thread");
+}
+
+#[test]
+fn get_thread_affinity_reports_all_cpus() {
+    let affinity = get_thread_affinity(thread_native_id());
+    assert!(affinity.is_ok());
+    assert!(!affinity.unwrap().is_empty());
+}
+
+#[test]
+fn set_thread_affinity_rejects_out_of_range_cpu() {
+    let result = set_thread_affinity(thread_native_id(), &[usize::MAX]);
+    assert!(matches!(result, Err(Error::Priority(_))));
+}
+
+#[test]
+fn set_thread_affinity_is_best_effort_only() {
+    // Haiku has no hard-pinning API, so even a valid request reports that
+    // the pinning itself could not be honored.
+    let result = set_thread_affinity(thread_native_id(), &[0]);
+    assert!(matches!(result, Err(Error::Priority(_))));
+}
+
+#[test]
+fn current_cpu_id_is_unsupported() {
+    assert!(matches!(current_cpu_id(), Err(Error::Priority(_))));
+}
+
+#[test]
+fn sporadic_policy_is_unsupported_on_haiku() {
+    let sporadic = RealtimeThreadSchedulePolicy::Sporadic {
+        sched_priority: HAIKU_MAX_PRIORITY,
+        low_priority: HAIKU_NORMAL_PRIORITY,
+        init_budget: std::time::Duration::from_millis(10),
+        repl_period: std::time::Duration::from_millis(100),
+        max_repl: 5,
+    };
+    let result = ThreadSchedulePolicy::Realtime(sporadic).to_posix();
+    assert!(matches!(result, Err(Error::Priority(_))));
+}
+
+#[test]
+fn thread_handle_can_target_another_thread() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let handle = std::thread::spawn(move || {
+        tx.send(thread_native_id()).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    });
+
+    let worker = ThreadHandle::new(rx.recv().unwrap());
+
+    assert!(worker.get_priority().is_ok());
+    assert!(worker
+        .set_priority(ThreadPriority::Crossplatform(50u8.try_into().unwrap()))
+        .is_ok());
+
+    handle.join().unwrap();
+}
+
 #[test]
 fn set_max_priority() {
     // Note: Setting max priority might require elevated privileges on Haiku
@@ -0,0 +1,171 @@
+//! Tests for the POSIX thread priority support shared by Linux, macOS and
+//! the BSDs.
+
+#![cfg(all(unix, not(target_os = "haiku")))]
+
+use rstest::rstest;
+use std::convert::TryInto;
+use thread_priority::*;
+
+#[test]
+fn get_current_thread_priority_works() {
+    assert!(get_current_thread_priority().is_ok());
+}
+
+#[test]
+fn get_thread_priority_works() {
+    let thread_id = thread_native_id();
+    assert!(get_thread_priority(thread_id).is_ok());
+}
+
+#[test]
+fn thread_schedule_policy_param_works() {
+    let thread_id = thread_native_id();
+    assert!(thread_schedule_policy_param(thread_id).is_ok());
+}
+
+#[rstest]
+fn get_and_set_priority_with_normal_policy(
+    #[values(ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Other))]
+    policy: ThreadSchedulePolicy,
+    #[values(
+        ThreadPriority::Min,
+        ThreadPriority::Crossplatform(23u8.try_into().unwrap()),
+        ThreadPriority::Crossplatform(50u8.try_into().unwrap())
+    )]
+    priority: ThreadPriority,
+) {
+    let result = set_thread_priority_and_policy(thread_native_id(), priority, policy);
+    assert!(result.is_ok());
+}
+
+#[rstest]
+fn named_priorities_resolve_to_allowed_values(
+    #[values(
+        NamedPriority::Idle,
+        NamedPriority::Lowest,
+        NamedPriority::Low,
+        NamedPriority::Normal,
+        NamedPriority::Display,
+        NamedPriority::UrgentDisplay,
+        NamedPriority::RealtimeDisplay,
+        NamedPriority::Urgent,
+        NamedPriority::Realtime
+    )]
+    named: NamedPriority,
+) {
+    let policy = ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Other);
+    let result = ThreadPriority::Named(named).to_posix(policy);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn named_priority_bands_are_ordered() {
+    // Named bands only have room to differ when the active policy's
+    // min/max priority actually span a range; some sandboxes report a
+    // degenerate (e.g. 0..=0) range for every policy, so this only checks
+    // that bands are never inverted, not that they're strictly increasing.
+    let policy = ThreadSchedulePolicy::Realtime(RealtimeThreadSchedulePolicy::Fifo);
+    let idle = ThreadPriority::Named(NamedPriority::Idle)
+        .to_posix(policy)
+        .unwrap();
+    let normal = ThreadPriority::Named(NamedPriority::Normal)
+        .to_posix(policy)
+        .unwrap();
+    let realtime = ThreadPriority::Named(NamedPriority::Realtime)
+        .to_posix(policy)
+        .unwrap();
+
+    assert!(idle <= normal);
+    assert!(normal <= realtime);
+}
+
+#[test]
+fn set_and_get_current_thread_priority() {
+    let normal_policy = ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Other);
+    let result = set_thread_priority_and_policy(
+        thread_native_id(),
+        ThreadPriority::Crossplatform(50u8.try_into().unwrap()),
+        normal_policy,
+    );
+    assert!(result.is_ok());
+    assert!(get_current_thread_priority().is_ok());
+}
+
+#[test]
+fn thread_ext_trait_works() {
+    let thread = std::thread::current();
+    assert!(thread.get_priority().is_ok());
+    assert_eq!(
+        thread.get_schedule_policy().unwrap(),
+        ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Other)
+    );
+    assert!(thread.get_schedule_policy_param().is_ok());
+}
+
+#[test]
+fn thread_ext_get_native_id_rejects_other_threads() {
+    let handle = std::thread::spawn(|| {});
+    let other = handle.thread().clone();
+    assert!(matches!(other.get_native_id(), Err(Error::Priority(_))));
+    handle.join().unwrap();
+}
+
+#[test]
+fn sporadic_policy_is_rejected_everywhere_it_is_unsupported() {
+    let sporadic = RealtimeThreadSchedulePolicy::Sporadic {
+        sched_priority: 50,
+        low_priority: 10,
+        init_budget: std::time::Duration::from_millis(10),
+        repl_period: std::time::Duration::from_millis(100),
+        max_repl: 5,
+    };
+    let result = ThreadSchedulePolicy::Realtime(sporadic).to_posix();
+    assert!(matches!(result, Err(Error::Priority(_))));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn set_and_get_thread_affinity_round_trips() {
+    let thread_id = thread_native_id();
+    let available = (0..libc::CPU_SETSIZE as usize)
+        .filter(|&cpu| {
+            get_thread_affinity(thread_id)
+                .map(|cpus| cpus.contains(&cpu))
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+    let target = vec![*available.first().unwrap_or(&0)];
+
+    assert!(set_thread_affinity(thread_id, &target).is_ok());
+    let affinity = get_thread_affinity(thread_id).unwrap();
+    assert_eq!(affinity, target);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn set_thread_affinity_rejects_out_of_range_cpu_without_panicking() {
+    let result = set_thread_affinity(thread_native_id(), &[99_999]);
+    assert!(matches!(result, Err(Error::Priority(_))));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn current_cpu_id_is_reported() {
+    assert!(current_cpu_id().is_ok());
+}
+
+#[cfg(target_os = "macos")]
+#[test]
+fn set_and_get_thread_affinity_tag_round_trips() {
+    let thread_id = thread_native_id();
+    assert!(set_thread_affinity(thread_id, &[0]).is_ok());
+    assert_eq!(get_thread_affinity(thread_id).unwrap(), vec![0]);
+}
+
+#[cfg(target_os = "macos")]
+#[test]
+fn set_thread_affinity_requires_at_least_one_cpu() {
+    let result = set_thread_affinity(thread_native_id(), &[]);
+    assert!(matches!(result, Err(Error::Priority(_))));
+}